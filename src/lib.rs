@@ -1,12 +1,13 @@
 //! Library functions for tomler. Keep logic here so tests can exercise it.
 
-use toml_edit::{DocumentMut, Item, Table, Value};
+use toml_edit::{ArrayOfTables, Datetime, DocumentMut, Entry, Item, Table, TableLike, Value};
 
 /// Infer a toml_edit::Value from a string input.
 /// Supports:
 /// - booleans ("true"/"false")
 /// - integer (i64)
 /// - float (f64)
+/// - datetimes (offset/local date-time, local date, local time)
 /// - simple arrays (comma separated, no quotes with commas: "1,2,3" or "a,b,c")
 /// - everything else -> string
 pub fn infer_value(s: &str) -> Value {
@@ -53,54 +54,587 @@ fn infer_single_value(s: &str) -> Value {
         Value::Integer(toml_edit::Formatted::new(i))
     } else if let Ok(f) = unquoted.parse::<f64>() {
         Value::Float(toml_edit::Formatted::new(f))
+    } else if !is_quoted_string(s_trim) && unquoted.parse::<Datetime>().is_ok() {
+        let dt = unquoted.parse::<Datetime>().unwrap();
+        Value::Datetime(toml_edit::Formatted::new(dt))
     } else {
         Value::String(toml_edit::Formatted::new(unquoted.to_string().into()))
     }
 }
 
+/// A single segment of a dot-notation path: either a table key or an
+/// integer index into an array or array-of-tables.
+///
+/// Both `servers.0.host` and `servers[0].host` parse to the same segments
+/// (`Key("servers")`, `Index(0)`, `Key("host")`).
+#[derive(Debug, PartialEq, Eq)]
+enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Split a dot-notation path into segments, recognizing `name[idx]` bracket
+/// suffixes and bare numeric segments (`name.idx`) as array indices.
+fn parse_path(key: &str) -> Vec<PathSegment<'_>> {
+    let mut segments = Vec::new();
+
+    for part in key.split('.') {
+        if let Some(bracket) = part.find('[') {
+            let (name, mut rest) = part.split_at(bracket);
+            if !name.is_empty() {
+                segments.push(PathSegment::Key(name));
+            }
+            while let Some(stripped) = rest.strip_prefix('[') {
+                let Some(end) = stripped.find(']') else {
+                    break;
+                };
+                if let Ok(idx) = stripped[..end].parse::<usize>() {
+                    segments.push(PathSegment::Index(idx));
+                }
+                rest = &stripped[end + 1..];
+            }
+        } else if let Ok(idx) = part.parse::<usize>() {
+            segments.push(PathSegment::Index(idx));
+        } else {
+            segments.push(PathSegment::Key(part));
+        }
+    }
+
+    segments
+}
+
+/// A position while walking a document: an `Item` (table/value/array-of-tables),
+/// a bare `Value` (reached by indexing into an inline array), or a `Table`
+/// (reached by indexing into an array-of-tables).
+enum Node<'a> {
+    Item(&'a Item),
+    Value(&'a Value),
+    Table(&'a Table),
+}
+
+impl<'a> Node<'a> {
+    fn get_key(&self, key: &str) -> Option<Node<'a>> {
+        match self {
+            Node::Item(item) => item.get(key).map(Node::Item),
+            Node::Table(table) => table.get(key).map(Node::Item),
+            Node::Value(Value::InlineTable(inline)) => inline.get(key).map(Node::Value),
+            Node::Value(_) => None,
+        }
+    }
+
+    fn get_index(&self, idx: usize) -> Option<Node<'a>> {
+        match self {
+            Node::Item(Item::ArrayOfTables(aot)) => aot.get(idx).map(Node::Table),
+            Node::Item(Item::Value(Value::Array(arr))) => arr.get(idx).map(Node::Value),
+            Node::Value(Value::Array(arr)) => arr.get(idx).map(Node::Value),
+            _ => None,
+        }
+    }
+
+    fn to_display_string(&self) -> String {
+        match self {
+            Node::Item(item) => item.to_string(),
+            Node::Value(value) => value.to_string(),
+            Node::Table(table) => table.to_string(),
+        }
+    }
+
+    fn span(&self) -> Option<std::ops::Range<usize>> {
+        match self {
+            Node::Item(item) => item.span(),
+            Node::Value(value) => value.span(),
+            Node::Table(table) => table.span(),
+        }
+    }
+
+    fn child_keys(&self) -> Option<Vec<String>> {
+        match self {
+            Node::Item(item) => item
+                .as_table_like()
+                .map(|t| t.iter().map(|(k, _)| k.to_string()).collect()),
+            Node::Table(table) => Some(table.iter().map(|(k, _)| k.to_string()).collect()),
+            Node::Value(Value::InlineTable(inline)) => {
+                Some(inline.iter().map(|(k, _)| k.to_string()).collect())
+            }
+            Node::Value(_) => None,
+        }
+    }
+}
+
+/// Walk a dot-notation path (with optional array/array-of-tables indexing)
+/// from the document root, returning the `Node` reached, if any.
+fn resolve<'a>(doc: &'a DocumentMut, key: &str) -> Option<Node<'a>> {
+    let mut node = Node::Item(doc.as_item());
+
+    for segment in parse_path(key) {
+        node = match segment {
+            PathSegment::Key(k) => node.get_key(k)?,
+            PathSegment::Index(i) => node.get_index(i)?,
+        };
+    }
+
+    Some(node)
+}
+
+/// Explicit value type for `coerce_value`, bypassing `infer_value`'s
+/// heuristics (e.g. to store `"5432"` as a string instead of an integer).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Type {
+    String,
+    Int,
+    Float,
+    Bool,
+    Datetime,
+    Array,
+    /// Route through `infer_value`'s existing heuristics.
+    Auto,
+}
+
+/// Build a `toml_edit::Value` of exactly the requested `Type`, bypassing
+/// `infer_value`'s heuristics. `Type::Auto` defers to `infer_value`.
+/// Returns an error if `s` can't be parsed as the requested type.
+pub fn coerce_value(s: &str, ty: Type) -> anyhow::Result<Value> {
+    let s_trim = s.trim();
+
+    match ty {
+        Type::Auto => Ok(infer_value(s)),
+        Type::String => Ok(Value::String(toml_edit::Formatted::new(s_trim.to_string()))),
+        Type::Int => s_trim
+            .parse::<i64>()
+            .map(|i| Value::Integer(toml_edit::Formatted::new(i)))
+            .map_err(|e| anyhow::anyhow!("cannot parse '{}' as an int: {}", s_trim, e)),
+        Type::Float => s_trim
+            .parse::<f64>()
+            .map(|f| Value::Float(toml_edit::Formatted::new(f)))
+            .map_err(|e| anyhow::anyhow!("cannot parse '{}' as a float: {}", s_trim, e)),
+        Type::Bool => {
+            if s_trim.eq_ignore_ascii_case("true") {
+                Ok(Value::Boolean(toml_edit::Formatted::new(true)))
+            } else if s_trim.eq_ignore_ascii_case("false") {
+                Ok(Value::Boolean(toml_edit::Formatted::new(false)))
+            } else {
+                Err(anyhow::anyhow!(
+                    "cannot parse '{}' as a bool (expected true/false)",
+                    s_trim
+                ))
+            }
+        }
+        Type::Datetime => s_trim
+            .parse::<Datetime>()
+            .map(|dt| Value::Datetime(toml_edit::Formatted::new(dt)))
+            .map_err(|e| anyhow::anyhow!("cannot parse '{}' as a datetime: {}", s_trim, e)),
+        Type::Array => {
+            let mut array = toml_edit::Array::new();
+            for part in s_trim.split(',').map(|p| p.trim()) {
+                array.push(infer_single_value(part));
+            }
+            Ok(Value::Array(array))
+        }
+    }
+}
+
 /// Get a textual representation of a key from a Document.
 /// Returns `Some` if key exists, else None.
 /// This prints raw TOML token for the value (so strings include quotes).
+/// Supports dot-notation paths, including array and array-of-tables indexing
+/// (`servers.0.host` or `servers[0].host`).
 pub fn get_value(doc: &DocumentMut, key: &str) -> Option<String> {
-    // Handle nested keys with dot notation
-    let parts: Vec<&str> = key.split('.').collect();
-    let mut current = doc.as_item();
+    resolve(doc, key).map(|node| node.to_display_string().trim().to_string())
+}
+
+/// Like `get_value`, but string values are returned without their enclosing
+/// quotes (used for the CLI's `--raw` mode). Other value types are unchanged.
+pub fn get_value_raw(doc: &DocumentMut, key: &str) -> Option<String> {
+    let node = resolve(doc, key)?;
+
+    let unquoted = match &node {
+        Node::Item(item) => item.as_str().map(|s| s.to_string()),
+        Node::Value(Value::String(s)) => Some(s.value().to_string()),
+        _ => None,
+    };
+
+    Some(unquoted.unwrap_or_else(|| node.to_display_string().trim().to_string()))
+}
+
+/// Convert a byte offset into a 1-based (line, column) position within `s`.
+fn line_col(s: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut line_start = 0;
+
+    for (i, c) in s.char_indices() {
+        if i >= offset {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            line_start = i + 1;
+        }
+    }
 
-    for part in parts {
-        current = current.get(part)?;
+    (line, offset.saturating_sub(line_start) + 1)
+}
+
+/// Render a `file:line:col` prefix plus the offending source line with a
+/// caret underline, using the span `toml_edit` retains on a parse error.
+pub fn format_parse_error(file: &str, raw: &str, err: &toml_edit::TomlError) -> String {
+    match err.span() {
+        Some(span) => {
+            let (line, col) = line_col(raw, span.start);
+            let source_line = raw.lines().nth(line - 1).unwrap_or("");
+            let caret_len = span.end.saturating_sub(span.start).max(1);
+            format!(
+                "{}:{}:{}: {}\n{}\n{}{}",
+                file,
+                line,
+                col,
+                err,
+                source_line,
+                " ".repeat(col - 1),
+                "^".repeat(caret_len)
+            )
+        }
+        None => format!("{}: {}", file, err),
     }
+}
 
-    Some(current.to_string().trim().to_string())
+/// Walk `key` against a freshly-parsed, span-retaining `ImDocument` (the
+/// already-loaded `DocumentMut` despans itself, per its `Table::span` doc
+/// comment), returning the name of the nearest ancestor the traversal
+/// reached and its span, if any.
+fn locate_ancestor(raw: &str, key: &str) -> (String, Option<std::ops::Range<usize>>) {
+    let Ok(spanned) = toml_edit::ImDocument::parse(raw.to_string()) else {
+        return ("<root>".to_string(), None);
+    };
+
+    let mut node = Node::Item(spanned.as_item());
+    let mut resolved = String::new();
+
+    for segment in parse_path(key) {
+        let next = match segment {
+            PathSegment::Key(k) => node.get_key(k),
+            PathSegment::Index(i) => node.get_index(i),
+        };
+        match next {
+            Some(n) => {
+                node = n;
+                if !resolved.is_empty() {
+                    resolved.push('.');
+                }
+                match segment {
+                    PathSegment::Key(k) => resolved.push_str(k),
+                    PathSegment::Index(i) => resolved.push_str(&i.to_string()),
+                }
+            }
+            None => break,
+        }
+    }
+
+    let ancestor = if resolved.is_empty() {
+        "<root>".to_string()
+    } else {
+        resolved
+    };
+    let span = node.span();
+    (ancestor, span)
+}
+
+/// Render a "key not found" error for `Get`/`Remove`, reporting the line and
+/// column where the nearest ancestor the path traversal reached before it
+/// stopped begins.
+pub fn format_path_error(file: &str, raw: &str, key: &str) -> String {
+    let (ancestor, span) = locate_ancestor(raw, key);
+
+    match span {
+        Some(span) => {
+            let (line, col) = line_col(raw, span.start);
+            let source_line = raw.lines().nth(line - 1).unwrap_or("");
+            format!(
+                "{}:{}:{}: key '{}' not found (traversal stopped at '{}')\n{}\n{}^",
+                file,
+                line,
+                col,
+                key,
+                ancestor,
+                source_line,
+                " ".repeat(col - 1)
+            )
+        }
+        None => format!(
+            "{}: key '{}' not found (traversal stopped at '{}')",
+            file, key, ancestor
+        ),
+    }
 }
 
 /// Set a nested (dot-notated) key in the Document, creating tables as necessary.
-/// Overwrites existing value at that key.
-pub fn set_nested_in_document(doc: &mut DocumentMut, key: &str, v: Value) {
-    let parts: Vec<&str> = key.split('.').collect();
-    assert!(!parts.is_empty(), "key must be non-empty");
-
-    // Walk/create tables
-    let last = parts.last().unwrap();
-    let mut table: &mut Table = doc.as_table_mut();
-
-    for part in &parts[..parts.len().saturating_sub(1)] {
-        // entry(part) returns an Entry. If missing, insert a Table.
-        // If there's a non-table item present at this key, replace it with a table.
-        let ent = table.entry(part);
-        match ent {
-            toml_edit::Entry::Vacant(vacant) => {
-                vacant.insert(Item::Table(Table::new()));
-            }
-            toml_edit::Entry::Occupied(mut occupied) => {
-                if !occupied.get().is_table() {
-                    occupied.insert(Item::Table(Table::new()));
+/// Overwrites existing value at that key. A numeric segment (`servers.0.host`
+/// or `servers[0].host`) creates/extends an `ArrayOfTables` at the preceding
+/// key, growing it with empty tables up to the requested index.
+pub fn set_nested_in_document(doc: &mut DocumentMut, key: &str, v: Value) -> anyhow::Result<()> {
+    let segments = parse_path(key);
+    if segments.is_empty() {
+        return Err(anyhow::anyhow!("key must be non-empty"));
+    }
+    set_path(doc.as_table_mut(), &segments, v)
+}
+
+fn set_path(table: &mut Table, segments: &[PathSegment], v: Value) -> anyhow::Result<()> {
+    match segments {
+        [] => Err(anyhow::anyhow!("key must be non-empty")),
+        [PathSegment::Key(last)] => {
+            table[*last] = Item::Value(v);
+            Ok(())
+        }
+        [PathSegment::Key(k), PathSegment::Index(idx), rest @ ..] => {
+            // entry(k) returns an Entry. If missing, create an array-of-tables;
+            // if it holds anything other than an array-of-tables, report an error.
+            let ent = table.entry(k);
+            match ent {
+                Entry::Vacant(vacant) => {
+                    vacant.insert(Item::ArrayOfTables(ArrayOfTables::new()));
+                }
+                Entry::Occupied(occupied) => {
+                    if !occupied.get().is_array_of_tables() {
+                        return Err(anyhow::anyhow!(
+                            "cannot index into '{}': it is not an array-of-tables (set '{}' directly to replace it)",
+                            k,
+                            k
+                        ));
+                    }
+                }
+            }
+            let aot = table[*k]
+                .as_array_of_tables_mut()
+                .expect("array-of-tables created above");
+
+            while aot.len() <= *idx {
+                aot.push(Table::new());
+            }
+            let entry_table = aot.get_mut(*idx).expect("grown above");
+
+            if rest.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "cannot assign a scalar directly to an array-of-tables element; use '{}[{}].<field>' instead",
+                    k,
+                    idx
+                ));
+            }
+            set_path(entry_table, rest, v)
+        }
+        [PathSegment::Key(k), rest @ ..] => {
+            let ent = table.entry(k);
+            match ent {
+                Entry::Vacant(vacant) => {
+                    vacant.insert(Item::Table(Table::new()));
+                }
+                Entry::Occupied(mut occupied) => {
+                    if !occupied.get().is_table() {
+                        occupied.insert(Item::Table(Table::new()));
+                    }
                 }
             }
+            let nested = table[*k].as_table_mut().expect("table created above");
+            set_path(nested, rest, v)
         }
-        table = table[part].as_table_mut().expect("table created above");
+        [PathSegment::Index(_), ..] => Err(anyhow::anyhow!(
+            "a path cannot start with an array index"
+        )),
     }
+}
 
-    table[*last] = Item::Value(v);
+/// Convert a loaded `DocumentMut` into a `serde_json::Value`: tables become
+/// JSON objects, arrays and arrays-of-tables become JSON arrays, and
+/// datetimes render as RFC3339 strings.
+pub fn document_to_json(doc: &DocumentMut) -> serde_json::Value {
+    table_like_to_json(doc.as_table())
+}
+
+fn item_to_json(item: &Item) -> serde_json::Value {
+    match item {
+        Item::None => serde_json::Value::Null,
+        Item::Value(v) => value_to_json(v),
+        Item::Table(t) => table_like_to_json(t),
+        Item::ArrayOfTables(aot) => {
+            serde_json::Value::Array(aot.iter().map(|t| table_like_to_json(t)).collect())
+        }
+    }
+}
+
+fn table_like_to_json(table: &dyn TableLike) -> serde_json::Value {
+    let map: serde_json::Map<String, serde_json::Value> = table
+        .iter()
+        .map(|(k, v)| (k.to_string(), item_to_json(v)))
+        .collect();
+    serde_json::Value::Object(map)
+}
+
+fn value_to_json(value: &Value) -> serde_json::Value {
+    match value {
+        Value::String(s) => serde_json::Value::String(s.value().clone()),
+        Value::Integer(i) => serde_json::Value::Number((*i.value()).into()),
+        Value::Float(f) => serde_json::Number::from_f64(*f.value())
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        Value::Boolean(b) => serde_json::Value::Bool(*b.value()),
+        Value::Datetime(dt) => serde_json::Value::String(dt.value().to_string()),
+        Value::Array(arr) => serde_json::Value::Array(arr.iter().map(value_to_json).collect()),
+        Value::InlineTable(it) => table_like_to_json(it),
+    }
+}
+
+/// Reconstruct a `DocumentMut` from a `serde_json::Value`. JSON objects
+/// become TOML tables, arrays of objects become arrays-of-tables, other
+/// arrays become inline arrays, and string leaves that parse as a TOML
+/// datetime are re-detected as such (mirroring `infer_value`).
+pub fn json_to_document(json: &serde_json::Value) -> anyhow::Result<DocumentMut> {
+    let mut doc = DocumentMut::new();
+    if let serde_json::Value::Object(map) = json {
+        for (k, v) in map {
+            doc[k] = json_to_item(v)?;
+        }
+    }
+    Ok(doc)
+}
+
+fn json_to_item(json: &serde_json::Value) -> anyhow::Result<Item> {
+    let item = match json {
+        serde_json::Value::Null => Item::None,
+        serde_json::Value::Bool(b) => Item::Value(Value::Boolean(toml_edit::Formatted::new(*b))),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Item::Value(Value::Integer(toml_edit::Formatted::new(i))),
+            None => Item::Value(Value::Float(toml_edit::Formatted::new(
+                n.as_f64().unwrap_or_default(),
+            ))),
+        },
+        serde_json::Value::String(s) => match s.parse::<Datetime>() {
+            Ok(dt) => Item::Value(Value::Datetime(toml_edit::Formatted::new(dt))),
+            Err(_) => Item::Value(Value::String(toml_edit::Formatted::new(s.clone()))),
+        },
+        serde_json::Value::Array(arr) => {
+            let all_objects = !arr.is_empty() && arr.iter().all(|v| v.is_object());
+            if all_objects {
+                let mut aot = ArrayOfTables::new();
+                for v in arr {
+                    let serde_json::Value::Object(map) = v else {
+                        unreachable!("all elements checked to be objects above")
+                    };
+                    aot.push(json_object_to_table(map)?);
+                }
+                Item::ArrayOfTables(aot)
+            } else if arr.iter().any(|v| v.is_object() || v.is_null()) {
+                return Err(anyhow::anyhow!(
+                    "cannot convert a heterogeneous or null-containing JSON array to TOML"
+                ));
+            } else {
+                let mut array = toml_edit::Array::new();
+                for v in arr {
+                    match json_to_item(v)? {
+                        Item::Value(val) => array.push(val),
+                        _ => {
+                            return Err(anyhow::anyhow!(
+                                "cannot convert a JSON array of objects nested inside another array to TOML"
+                            ))
+                        }
+                    }
+                }
+                Item::Value(Value::Array(array))
+            }
+        }
+        serde_json::Value::Object(map) => Item::Table(json_object_to_table(map)?),
+    };
+    Ok(item)
+}
+
+fn json_object_to_table(
+    map: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<Table> {
+    let mut table = Table::new();
+    for (k, v) in map {
+        table[k] = json_to_item(v)?;
+    }
+    Ok(table)
+}
+
+/// Which side wins when `merge_into` hits a scalar/type conflict.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Prefer {
+    Base,
+    Overlay,
+}
+
+/// Deep-merge `overlay` into `base`, recursing into matching sub-tables.
+/// On a scalar/type conflict, `prefer` selects which side's value survives.
+/// When `append_arrays` is set, conflicting arrays are concatenated (base's
+/// elements followed by overlay's) instead of being replaced.
+pub fn merge_into(base: &mut Table, overlay: &Table, prefer: Prefer, append_arrays: bool) {
+    for (key, overlay_item) in overlay.iter() {
+        let base_is_table = base.get(key).is_some_and(Item::is_table);
+        if base_is_table && overlay_item.is_table() {
+            let base_table = base.get_mut(key).unwrap().as_table_mut().unwrap();
+            merge_into(base_table, overlay_item.as_table().unwrap(), prefer, append_arrays);
+            continue;
+        }
+
+        let base_array = base
+            .get(key)
+            .and_then(Item::as_value)
+            .and_then(Value::as_array);
+        let overlay_array = overlay_item.as_value().and_then(Value::as_array);
+        if append_arrays {
+            if let (Some(_), Some(overlay_array)) = (base_array, overlay_array) {
+                let overlay_array = overlay_array.clone();
+                let base_array = base
+                    .get_mut(key)
+                    .unwrap()
+                    .as_value_mut()
+                    .and_then(Value::as_array_mut)
+                    .unwrap();
+                for v in overlay_array.iter() {
+                    base_array.push(v.clone());
+                }
+                continue;
+            }
+        }
+
+        if base.contains_key(key) && prefer == Prefer::Base {
+            continue;
+        }
+
+        base.insert(key, overlay_item.clone());
+    }
+}
+
+/// Remove a nested (dot-notated) key from the Document. An index segment
+/// selects an array-of-tables element to descend into (`servers.0.port`).
+/// Returns the removed `Item` if the key existed, else `None`; a path that
+/// ends at an array index (an array-of-tables element itself, or an element
+/// of an inline array) removes nothing and returns `None`.
+pub fn remove_nested_in_document(doc: &mut DocumentMut, key: &str) -> Option<Item> {
+    remove_path(doc.as_table_mut(), &parse_path(key))
+}
+
+fn remove_path(table: &mut dyn TableLike, segments: &[PathSegment]) -> Option<Item> {
+    match segments {
+        [] => None,
+        [PathSegment::Key(k)] => table.remove(k),
+        [PathSegment::Key(k), PathSegment::Index(idx), rest @ ..] => {
+            let aot = table.get_mut(k)?.as_array_of_tables_mut()?;
+            remove_path(aot.get_mut(*idx)?, rest)
+        }
+        [PathSegment::Key(k), rest @ ..] => remove_path(table.get_mut(k)?.as_table_mut()?, rest),
+        [PathSegment::Index(_), ..] => None,
+    }
+}
+
+/// List the child key names at a dot-notated (array-aware) path (`None` for
+/// the document root). Returns `None` if the path doesn't resolve to a
+/// table-like item.
+pub fn list_keys(doc: &DocumentMut, prefix: Option<&str>) -> Option<Vec<String>> {
+    let node = match prefix {
+        Some(prefix) => resolve(doc, prefix)?,
+        None => Node::Item(doc.as_item()),
+    };
+    node.child_keys()
 }
 
 #[cfg(test)]
@@ -166,17 +700,319 @@ mod tests {
 host = "localhost"
 "#;
         let mut doc: DocumentMut = src.parse().unwrap();
-        set_nested_in_document(&mut doc, "server.port", Value::Integer(toml_edit::Formatted::new(8000)));
+        set_nested_in_document(&mut doc, "server.port", Value::Integer(toml_edit::Formatted::new(8000))).unwrap();
         // check - use our getter function which handles nested keys
         assert!(get_value(&doc, "server.port").is_some());
         assert_eq!(get_value(&doc, "server.port").unwrap(), "8000");
 
         // create nested deeper - simple array
-        set_nested_in_document(&mut doc, "servers.main.ports", infer_value("80,443"));
+        set_nested_in_document(&mut doc, "servers.main.ports", infer_value("80,443")).unwrap();
         assert!(get_value(&doc, "servers.main.ports").is_some());
         assert_eq!(get_value(&doc, "servers.main.ports").unwrap(), "[80, 443]");
     }
 
+    #[test]
+    fn get_and_set_array_of_tables_indexing() {
+        let src = r#"# servers
+[[servers]]
+host = "10.0.0.1"
+
+[[servers]]
+host = "10.0.0.2"
+"#;
+        let mut doc: DocumentMut = src.parse().unwrap();
+
+        // dot and bracket syntax both read the same element
+        assert_eq!(get_value(&doc, "servers.0.host").unwrap(), "\"10.0.0.1\"");
+        assert_eq!(get_value(&doc, "servers[1].host").unwrap(), "\"10.0.0.2\"");
+
+        // out-of-range index is a clean miss, not a panic
+        assert!(get_value(&doc, "servers.2.host").is_none());
+
+        // writing an existing element preserves the rest of the document
+        set_nested_in_document(&mut doc, "servers[0].host", infer_value("10.0.0.9")).unwrap();
+        assert_eq!(get_value(&doc, "servers.0.host").unwrap(), "\"10.0.0.9\"");
+        assert!(doc.to_string().contains("# servers"));
+
+        // writing past the end grows the array-of-tables with empty tables
+        set_nested_in_document(&mut doc, "servers[3].host", infer_value("10.0.0.4")).unwrap();
+        assert_eq!(get_value(&doc, "servers.3.host").unwrap(), "\"10.0.0.4\"");
+        assert!(get_value(&doc, "servers.2.host").is_none());
+    }
+
+    #[test]
+    fn set_nested_reports_errors_instead_of_panicking() {
+        let mut doc: DocumentMut = "[[servers]]\nhost = \"10.0.0.1\"\n".parse().unwrap();
+
+        // assigning a scalar straight to an array-of-tables element has no
+        // field to write into
+        assert!(set_nested_in_document(&mut doc, "servers[0]", infer_value("foo")).is_err());
+
+        // a path cannot start with a bare index
+        assert!(set_nested_in_document(&mut doc, "0", infer_value("foo")).is_err());
+    }
+
+    #[test]
+    fn set_nested_rejects_indexing_a_scalar() {
+        let mut doc: DocumentMut = "name = \"hello\"\n".parse().unwrap();
+
+        assert!(set_nested_in_document(&mut doc, "name[0].x", infer_value("foo")).is_err());
+        // the original value must survive the failed attempt
+        assert_eq!(get_value(&doc, "name").unwrap(), "\"hello\"");
+    }
+
+    #[test]
+    fn get_inline_array_indexing() {
+        let mut doc: DocumentMut = "ports = [80, 443, 8080]\n".parse().unwrap();
+        assert_eq!(get_value(&doc, "ports.1").unwrap(), "443");
+        assert_eq!(get_value(&doc, "ports[2]").unwrap(), "8080");
+        assert!(get_value(&doc, "ports.5").is_none());
+
+        set_nested_in_document(&mut doc, "other", infer_value("1,2")).unwrap();
+        assert_eq!(get_value(&doc, "other[0]").unwrap(), "1");
+    }
+
+    #[test]
+    fn format_parse_error_points_at_the_offending_line() {
+        let raw = "name = \"app\"\nport = \n";
+        let err = raw.parse::<DocumentMut>().unwrap_err();
+        let rendered = format_parse_error("config.toml", raw, &err);
+        assert!(rendered.starts_with("config.toml:2:"));
+        assert!(rendered.contains("port = "));
+        assert!(rendered.contains('^'));
+    }
+
+    #[test]
+    fn format_path_error_reports_nearest_ancestor() {
+        let raw = "[server]\nhost = \"localhost\"\n";
+        let rendered = format_path_error("config.toml", raw, "server.missing.deeper");
+        assert!(rendered.contains("stopped at 'server'"));
+        assert!(rendered.starts_with("config.toml:1:"));
+
+        // a path that fails at the very first segment stops at the root
+        let rendered_root = format_path_error("config.toml", raw, "nope");
+        assert!(rendered_root.contains("stopped at '<root>'"));
+    }
+
+    #[test]
+    fn merge_into_recurses_nested_tables() {
+        let mut base: DocumentMut = r#"
+[server]
+host = "localhost"
+port = 8000
+"#
+        .parse()
+        .unwrap();
+        let overlay: DocumentMut = r#"
+[server]
+port = 9000
+timeout = 30
+"#
+        .parse()
+        .unwrap();
+
+        merge_into(
+            base.as_table_mut(),
+            overlay.as_table(),
+            Prefer::Overlay,
+            false,
+        );
+
+        assert_eq!(get_value(&base, "server.host").unwrap(), "\"localhost\"");
+        assert_eq!(get_value(&base, "server.port").unwrap(), "9000");
+        assert_eq!(get_value(&base, "server.timeout").unwrap(), "30");
+    }
+
+    #[test]
+    fn merge_into_respects_prefer_and_append_arrays() {
+        let mut base: DocumentMut = "tags = [\"a\"]\nname = \"base\"\n".parse().unwrap();
+        let overlay: DocumentMut = "tags = [\"b\"]\nname = \"overlay\"\n".parse().unwrap();
+
+        // prefer base: conflicting scalar keeps the base value
+        let mut prefer_base = base.clone();
+        merge_into(
+            prefer_base.as_table_mut(),
+            overlay.as_table(),
+            Prefer::Base,
+            false,
+        );
+        assert_eq!(get_value(&prefer_base, "name").unwrap(), "\"base\"");
+        // without --append-arrays, a conflicting array is resolved like a
+        // scalar, so prefer base keeps the base array
+        assert_eq!(get_value(&prefer_base, "tags").unwrap(), "[\"a\"]");
+
+        // append-arrays concatenates instead of replacing
+        merge_into(
+            base.as_table_mut(),
+            overlay.as_table(),
+            Prefer::Overlay,
+            true,
+        );
+        assert_eq!(get_value(&base, "name").unwrap(), "\"overlay\"");
+        assert_eq!(get_value(&base, "tags").unwrap(), "[\"a\", \"b\"]");
+    }
+
+    #[test]
+    fn coerce_value_bypasses_inference() {
+        // Explicit string keeps digits/leading zeros as a string
+        assert_eq!(
+            coerce_value("5432", Type::String).unwrap().as_str().unwrap(),
+            "5432"
+        );
+        assert_eq!(
+            coerce_value("01", Type::String).unwrap().as_str().unwrap(),
+            "01"
+        );
+
+        // Explicit types parse successfully
+        assert_eq!(coerce_value("42", Type::Int).unwrap().as_integer().unwrap(), 42);
+        assert!((coerce_value("3.14", Type::Float).unwrap().as_float().unwrap() - 3.14).abs() < 1e-10);
+        assert!(coerce_value("true", Type::Bool).unwrap().as_bool().unwrap());
+        assert!(coerce_value("1979-05-27", Type::Datetime).unwrap().as_datetime().is_some());
+        assert_eq!(coerce_value("1,2,3", Type::Array).unwrap().as_array().unwrap().len(), 3);
+
+        // Auto still routes through infer_value
+        assert_eq!(coerce_value("5432", Type::Auto).unwrap().as_integer().unwrap(), 5432);
+
+        // Mismatched types report a helpful error instead of panicking
+        assert!(coerce_value("abc", Type::Int).is_err());
+        assert!(coerce_value("abc", Type::Bool).is_err());
+        assert!(coerce_value("not-a-date", Type::Datetime).is_err());
+    }
+
+    #[test]
+    fn document_to_json_converts_tables_arrays_and_datetimes() {
+        let src = r#"name = "app"
+retries = 3
+[server]
+host = "localhost"
+tags = ["a", "b"]
+
+[[server.replicas]]
+host = "r1"
+
+[[server.replicas]]
+host = "r2"
+"#;
+        let doc: DocumentMut = src.parse().unwrap();
+        let json = document_to_json(&doc);
+
+        assert_eq!(json["name"], serde_json::json!("app"));
+        assert_eq!(json["retries"], serde_json::json!(3));
+        assert_eq!(json["server"]["host"], serde_json::json!("localhost"));
+        assert_eq!(json["server"]["tags"], serde_json::json!(["a", "b"]));
+        assert_eq!(
+            json["server"]["replicas"],
+            serde_json::json!([{"host": "r1"}, {"host": "r2"}])
+        );
+    }
+
+    #[test]
+    fn json_to_document_round_trips_through_toml() {
+        let json = serde_json::json!({
+            "name": "app",
+            "server": {
+                "host": "localhost",
+                "port": 8080,
+                "replicas": [{"host": "r1"}, {"host": "r2"}]
+            }
+        });
+
+        let doc = json_to_document(&json).unwrap();
+        assert_eq!(get_value(&doc, "name").unwrap(), "\"app\"");
+        assert_eq!(get_value(&doc, "server.port").unwrap(), "8080");
+        assert_eq!(
+            get_value(&doc, "server.replicas.1.host").unwrap(),
+            "\"r2\""
+        );
+
+        // round-tripping back to JSON recovers the same structure
+        let round_tripped = document_to_json(&doc);
+        assert_eq!(round_tripped, json);
+    }
+
+    #[test]
+    fn json_to_document_rejects_mixed_and_null_arrays() {
+        assert!(json_to_document(&serde_json::json!({"mixed": [1, {"a": 1}, 2]})).is_err());
+        assert!(json_to_document(&serde_json::json!({"nulls": [1, null, 2]})).is_err());
+    }
+
+    #[test]
+    fn remove_nested_removes_value() {
+        let src = r#"[server]
+host = "localhost"
+port = 8000
+"#;
+        let mut doc: DocumentMut = src.parse().unwrap();
+        assert!(remove_nested_in_document(&mut doc, "server.port").is_some());
+        assert!(get_value(&doc, "server.port").is_none());
+        assert!(get_value(&doc, "server.host").is_some());
+
+        // removing a missing key is a no-op that reports absence
+        assert!(remove_nested_in_document(&mut doc, "server.missing").is_none());
+        assert!(remove_nested_in_document(&mut doc, "nope.nope").is_none());
+    }
+
+    #[test]
+    fn list_keys_at_root_and_nested() {
+        let src = r#"name = "app"
+[database]
+host = "localhost"
+port = 5432
+"#;
+        let doc: DocumentMut = src.parse().unwrap();
+
+        let root_keys = list_keys(&doc, None).unwrap();
+        assert!(root_keys.contains(&"name".to_string()));
+        assert!(root_keys.contains(&"database".to_string()));
+
+        let db_keys = list_keys(&doc, Some("database")).unwrap();
+        assert!(db_keys.contains(&"host".to_string()));
+        assert!(db_keys.contains(&"port".to_string()));
+
+        assert!(list_keys(&doc, Some("nonexistent")).is_none());
+    }
+
+    #[test]
+    fn remove_and_list_keys_through_array_of_tables() {
+        let src = r#"[[servers]]
+host = "10.0.0.1"
+port = 80
+
+[[servers]]
+host = "10.0.0.2"
+port = 81
+"#;
+        let mut doc: DocumentMut = src.parse().unwrap();
+
+        let keys = list_keys(&doc, Some("servers.0")).unwrap();
+        assert!(keys.contains(&"host".to_string()));
+        assert!(keys.contains(&"port".to_string()));
+
+        assert!(remove_nested_in_document(&mut doc, "servers.0.port").is_some());
+        assert!(get_value(&doc, "servers.0.port").is_none());
+        assert!(get_value(&doc, "servers[1].port").is_some());
+    }
+
+    #[test]
+    fn infer_datetimes() {
+        // Offset date-time
+        assert!(infer_value("1979-05-27T07:32:00Z").as_datetime().is_some());
+        assert!(infer_value("1979-05-27T07:32:00+07:00").as_datetime().is_some());
+        // Local date-time
+        assert!(infer_value("1979-05-27T07:32:00").as_datetime().is_some());
+        // Local date
+        assert!(infer_value("1979-05-27").as_datetime().is_some());
+        // Local time
+        assert!(infer_value("07:32:00").as_datetime().is_some());
+
+        // Quoted datetimes should stay strings
+        assert_eq!(
+            infer_value("\"1979-05-27\"").as_str().unwrap(),
+            "1979-05-27"
+        );
+    }
+
     #[test]
     fn simple_array_parsing() {
         // Test the simplified array parsing
@@ -2,10 +2,15 @@ use clap::{Parser, Subcommand};
 use std::fs;
 use std::process;
 
-use tomler::{get_value, infer_value, set_nested_in_document};
+use tomler::{
+    coerce_value, document_to_json, format_parse_error, format_path_error, get_value,
+    get_value_raw, json_to_document, list_keys, merge_into, remove_nested_in_document,
+    set_nested_in_document, Prefer, Type,
+};
 
 #[derive(Parser)]
 #[command(name = "tomler")]
+#[command(version)]
 #[command(about = "Edit TOML files in-place with simple type inference and nested keys")]
 struct Cli {
     /// TOML file path (default: config.toml)
@@ -28,65 +33,161 @@ enum Commands {
     },
 
     /// Set a value by key (dot notation)
-    Set { key: String, value: String },
+    Set {
+        key: String,
+        value: String,
+        /// Explicit value type, bypassing type inference
+        #[arg(long = "type", value_enum, default_value = "auto")]
+        ty: Type,
+    },
+
+    /// List child key names at a path (top-level if no prefix given)
+    Keys {
+        /// Key prefix to list children of (supports dot notation)
+        prefix: Option<String>,
+    },
+
+    /// Remove a value by key (dot notation)
+    Remove { key: String },
+
+    /// Deep-merge another TOML file into this one
+    Merge {
+        /// Path to the TOML file to merge in
+        other: String,
+        /// Which side wins on scalar/type conflicts
+        #[arg(long, value_enum, default_value = "overlay")]
+        prefer: Prefer,
+        /// Concatenate conflicting arrays instead of replacing them
+        #[arg(long)]
+        append_arrays: bool,
+    },
+
+    /// Convert the document to/from JSON
+    Convert {
+        /// Target format to convert to
+        #[arg(long, value_enum)]
+        to: Format,
+    },
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Format {
+    /// Read the file as TOML, print it as JSON on stdout
+    Json,
+    /// Read the file as JSON, print it as TOML on stdout
+    Toml,
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
-    let raw = fs::read_to_string(&cli.file)
-        .map_err(|e| anyhow::anyhow!("failed to read {}: {}", cli.file, e))?;
+    // `convert --to toml` ingests JSON rather than TOML, so it needs to
+    // bypass the generic TOML load below.
+    if let Commands::Convert { to: Format::Toml } = &cli.command {
+        let raw = fs::read_to_string(&cli.file)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", cli.file, e))?;
+        let json: serde_json::Value = serde_json::from_str(&raw)
+            .map_err(|e| anyhow::anyhow!("failed to parse json file {}: {}", cli.file, e))?;
+        print!("{}", json_to_document(&json)?);
+        return Ok(());
+    }
+
+    // `set` is expected to create the file on first use, so an absent file
+    // is treated as an empty document rather than a read error.
+    let file_exists = std::path::Path::new(&cli.file).exists();
+    let source = if !file_exists && matches!(cli.command, Commands::Set { .. }) {
+        String::new()
+    } else {
+        fs::read_to_string(&cli.file)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", cli.file, e))?
+    };
 
-    let mut doc: toml_edit::DocumentMut = raw
-        .parse()
-        .map_err(|e| anyhow::anyhow!("failed to parse toml file {}: {}", cli.file, e))?;
+    let mut doc: toml_edit::DocumentMut = match source.parse() {
+        Ok(doc) => doc,
+        Err(e) => {
+            eprintln!("{}", format_parse_error(&cli.file, &source, &e));
+            process::exit(2);
+        }
+    };
 
     match cli.command {
         Commands::Get { key, raw } => {
-            // Use dot-notation traversal so we can conditionally format strings
-            let parts: Vec<&str> = key.split('.').collect();
-            let mut current = doc.as_item();
-
-            for part in parts {
-                match current.get(part) {
-                    Some(next) => current = next,
-                    None => {
-                        eprintln!("Key not found: {}", key);
-                        process::exit(2);
-                    }
-                }
-            }
-
-            if raw {
-                // If it's a string value, print without quotes; otherwise, print normally
-                if let Some(val) = current.as_value() {
-                    if let Some(s) = val.as_str() {
-                        println!("{}", s);
-                        return Ok(());
-                    }
-                }
-                // Fallback for non-strings: same as default representation
-                println!("{}", current.to_string().trim());
-                return Ok(());
+            let value = if raw {
+                get_value_raw(&doc, &key)
             } else {
-                // Default behavior: print TOML token (strings include quotes)
-                match get_value(&doc, &key) {
-                    Some(s) => {
-                        println!("{}", s);
-                        return Ok(());
-                    }
-                    None => {
-                        eprintln!("Key not found: {}", key);
-                        process::exit(2);
-                    }
+                get_value(&doc, &key)
+            };
+            match value {
+                Some(s) => {
+                    println!("{}", s);
+                    return Ok(());
                 }
+                None => {
+                    eprintln!("{}", format_path_error(&cli.file, &source, &key));
+                    process::exit(2);
+                }
+            }
+        }
+        Commands::Set { key, value, ty } => {
+            let result = coerce_value(&value, ty)
+                .and_then(|v| set_nested_in_document(&mut doc, &key, v));
+            if let Err(e) = result {
+                eprintln!("{}: {}", cli.file, e);
+                process::exit(2);
             }
+            fs::write(&cli.file, doc.to_string())
+                .map_err(|e| anyhow::anyhow!("failed to write {}: {}", cli.file, e))?;
+            println!("Set '{}' = '{}'", key, value);
         }
-        Commands::Set { key, value } => {
-            let v = infer_value(&value);
-            set_nested_in_document(&mut doc, &key, v);
+        Commands::Keys { prefix } => match list_keys(&doc, prefix.as_deref()) {
+            Some(keys) => {
+                for k in keys {
+                    println!("{}", k);
+                }
+            }
+            None => {
+                let key = prefix.unwrap_or_default();
+                eprintln!("{}", format_path_error(&cli.file, &source, &key));
+                process::exit(2);
+            }
+        },
+        Commands::Remove { key } => match remove_nested_in_document(&mut doc, &key) {
+            Some(_) => {
+                fs::write(&cli.file, doc.to_string())
+                    .map_err(|e| anyhow::anyhow!("failed to write {}: {}", cli.file, e))?;
+                println!("Removed '{}'", key);
+            }
+            None => {
+                eprintln!("{}", format_path_error(&cli.file, &source, &key));
+                process::exit(2);
+            }
+        },
+        Commands::Merge {
+            other,
+            prefer,
+            append_arrays,
+        } => {
+            let other_raw = fs::read_to_string(&other)
+                .map_err(|e| anyhow::anyhow!("failed to read {}: {}", other, e))?;
+            let overlay_doc: toml_edit::DocumentMut = match other_raw.parse() {
+                Ok(doc) => doc,
+                Err(e) => {
+                    eprintln!("{}", format_parse_error(&other, &other_raw, &e));
+                    process::exit(2);
+                }
+            };
+
+            merge_into(doc.as_table_mut(), overlay_doc.as_table(), prefer, append_arrays);
             fs::write(&cli.file, doc.to_string())
                 .map_err(|e| anyhow::anyhow!("failed to write {}: {}", cli.file, e))?;
+            println!("Merged '{}' into '{}'", other, cli.file);
+        }
+        Commands::Convert { to: Format::Json } => {
+            let json = document_to_json(&doc);
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Commands::Convert { to: Format::Toml } => {
+            unreachable!("handled before the TOML file is loaded")
         }
     }
 
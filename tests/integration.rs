@@ -19,7 +19,7 @@ name = "example"
     let mut doc: DocumentMut = raw.parse().unwrap();
 
     // set new nested key
-    set_nested_in_document(&mut doc, "app.retries", infer_value("5"));
+    set_nested_in_document(&mut doc, "app.retries", infer_value("5")).unwrap();
     // write back
     fs::write(f.path(), doc.to_string()).expect("write back");
 
@@ -30,6 +30,45 @@ name = "example"
     assert!(after.contains("# initial config"));
 }
 
+#[test]
+fn cli_set_with_explicit_type() {
+    let f = NamedTempFile::new().expect("create temp file");
+    fs::write(f.path(), "").expect("write empty file");
+
+    // --type string keeps digits as a string instead of inferring an integer
+    Command::cargo_bin("tomler")
+        .unwrap()
+        .args([
+            "--file",
+            f.path().to_str().unwrap(),
+            "set",
+            "--type",
+            "string",
+            "port",
+            "5432",
+        ])
+        .assert()
+        .success();
+    let content = fs::read_to_string(f.path()).unwrap();
+    assert!(content.contains("port = \"5432\""));
+
+    // --type int on an unparsable value fails with a helpful message
+    Command::cargo_bin("tomler")
+        .unwrap()
+        .args([
+            "--file",
+            f.path().to_str().unwrap(),
+            "set",
+            "--type",
+            "int",
+            "port",
+            "abc",
+        ])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot parse"));
+}
+
 #[test]
 fn cli_get_default_and_raw_modes() {
     // Prepare a temp config file
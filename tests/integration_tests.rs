@@ -56,9 +56,16 @@ fn test_cli_basic_operations() -> Result<()> {
         .output()?;
     assert!(output.status.success());
 
-    // Test getting values
+    // Test getting values (--raw strips the quotes the default mode keeps
+    // around strings; see tests/integration.rs::cli_get_default_and_raw_modes)
     let output = Command::new(env!("CARGO_BIN_EXE_tomler"))
-        .args(&["--file", toml_file.to_str().unwrap(), "get", "name"])
+        .args(&[
+            "--file",
+            toml_file.to_str().unwrap(),
+            "get",
+            "name",
+            "--raw",
+        ])
         .output()?;
     assert!(output.status.success());
     assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "test-app");
@@ -69,6 +76,7 @@ fn test_cli_basic_operations() -> Result<()> {
             toml_file.to_str().unwrap(),
             "get",
             "database.host",
+            "--raw",
         ])
         .output()?;
     assert!(output.status.success());
@@ -134,6 +142,23 @@ fn test_cli_error_handling() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_cli_parse_error_reports_location() -> Result<()> {
+    let temp_dir = TempDir::new()?;
+    let toml_file = temp_dir.path().join("broken.toml");
+    fs::write(&toml_file, "name = \"app\"\nport = \n")?;
+
+    let output = Command::new(env!("CARGO_BIN_EXE_tomler"))
+        .args(&["--file", toml_file.to_str().unwrap(), "get", "name"])
+        .output()?;
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("broken.toml:2:"));
+    assert!(stderr.contains('^'));
+
+    Ok(())
+}
+
 #[test]
 fn test_cli_help_and_version() -> Result<()> {
     // Test --help
@@ -142,7 +167,7 @@ fn test_cli_help_and_version() -> Result<()> {
         .output()?;
     assert!(output.status.success());
     let help_text = String::from_utf8_lossy(&output.stdout);
-    assert!(help_text.contains("A simple lightweight TOML get/set tool"));
+    assert!(help_text.contains("Edit TOML files in-place with simple type inference and nested keys"));
     assert!(help_text.contains("Commands:"));
     assert!(help_text.contains("get"));
     assert!(help_text.contains("set"));